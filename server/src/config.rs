@@ -0,0 +1,17 @@
+use crate::io::noise::NoiseConfig;
+use crate::ping_manager::PingConfig;
+
+/// Netcode-protocol level settings, independent of any particular transport.
+#[derive(Clone, Debug, Default)]
+pub struct NetcodeConfig {
+    pub protocol_id: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct ServerConfig {
+    pub netcode: NetcodeConfig,
+    pub ping: PingConfig,
+    /// Opt into a Noise handshake and per-packet encryption/authentication over the raw UDP
+    /// transport; disabled (plaintext) by default.
+    pub noise: NoiseConfig,
+}
@@ -0,0 +1,141 @@
+use crate::ping_manager::{PingConfig, PingManager};
+use bytes::Bytes;
+use lightyear_shared::channel::{self, ChannelContainer, ChannelKind, ChannelSettings, NetworkRole};
+use lightyear_shared::tick::Tick;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+/// Per-client connection state: everything the server needs to track to talk to a single
+/// connected peer.
+pub(crate) struct Connection {
+    pub address: SocketAddr,
+    pub ping_manager: PingManager,
+    pub channels: HashMap<ChannelKind, ChannelContainer>,
+}
+
+impl Connection {
+    pub fn new(address: SocketAddr, ping_config: PingConfig) -> Self {
+        Self {
+            address,
+            ping_manager: PingManager::new(ping_config),
+            channels: HashMap::new(),
+        }
+    }
+
+    /// Build this connection's half of a channel and start tracking it, seeded with
+    /// whatever RTT estimate the ping manager already has.
+    pub fn add_channel(&mut self, kind: ChannelKind, settings: ChannelSettings) {
+        let mut channel = ChannelContainer::new(settings, NetworkRole::Server);
+        channel.set_rtt_estimate(self.ping_manager.rtt_estimate());
+        self.channels.insert(kind, channel);
+    }
+
+    /// Push the ping manager's current RTT estimate out to every channel's sender, so a
+    /// reliable channel's resend timeout tracks the measured RTT instead of staying on
+    /// [`FALLBACK_RESEND_TIMEOUT`](lightyear_shared::channel::senders::reliable). Called
+    /// once per server update.
+    pub fn sync_rtt_estimate(&mut self) {
+        let rtt_estimate = self.ping_manager.rtt_estimate();
+        for channel in self.channels.values_mut() {
+            channel.set_rtt_estimate(rtt_estimate.clone());
+        }
+    }
+
+    /// Decode a received application payload and route it to the channel its header names.
+    /// Dropped silently if the packet is malformed or names a channel this connection doesn't
+    /// track.
+    pub fn receive_packet(&mut self, data: Bytes) {
+        channel::receive_packet(&mut self.channels, data);
+    }
+
+    /// Drain the messages ready to be delivered to the application for every channel that
+    /// holds a receiver, keyed by the channel they arrived on.
+    pub fn read_messages(&mut self) -> HashMap<ChannelKind, Vec<Bytes>> {
+        self.channels
+            .iter_mut()
+            .map(|(kind, channel)| (*kind, channel.read_messages()))
+            .filter(|(_, messages)| !messages.is_empty())
+            .collect()
+    }
+
+    /// Collect every channel's outgoing packets for this tick, ready to hand to the transport.
+    pub fn flush_channels(&mut self) -> Vec<Bytes> {
+        self.channels
+            .iter_mut()
+            .flat_map(|(kind, channel)| channel::flush_channel(*kind, channel))
+            .collect()
+    }
+
+    /// Per-tick bookkeeping: advance every channel's current tick and evict stale reassembly
+    /// state, so unreliable reassembly buffers and tick-buffered channels stay bounded.
+    pub fn update(&mut self, now: Instant, tick: Tick) {
+        for channel in self.channels.values_mut() {
+            channel.advance_tick(tick);
+            channel.update(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lightyear_shared::channel::{ChannelDirection, ChannelMode};
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn addr() -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 12345)
+    }
+
+    fn bidirectional_settings() -> ChannelSettings {
+        ChannelSettings {
+            mode: ChannelMode::UnorderedUnreliable,
+            direction: ChannelDirection::Bidirectional,
+        }
+    }
+
+    #[test]
+    fn sync_rtt_estimate_runs_over_every_channel_after_an_rtt_update() {
+        let mut connection = Connection::new(addr(), PingConfig::default());
+        let kind = ChannelKind::new(1);
+        connection.add_channel(kind, bidirectional_settings());
+        // the channel was seeded with whatever estimate the ping manager had at add_channel
+        // time; record an RTT sample afterwards so sync_rtt_estimate has a changed estimate
+        // to push out
+        let sent_at = Instant::now();
+        let id = connection.ping_manager.send_ping(sent_at);
+        connection
+            .ping_manager
+            .receive_pong(id, sent_at + std::time::Duration::from_millis(50));
+        connection.sync_rtt_estimate();
+        // no direct getter on the channel's sender RTT estimate (and this unreliable channel
+        // ignores it), so this only checks the call completes and leaves the channel tracked
+        assert!(connection.channels.contains_key(&kind));
+    }
+
+    #[test]
+    fn receive_packet_routes_a_well_formed_packet_to_its_channel_and_read_messages_returns_it() {
+        let mut connection = Connection::new(addr(), PingConfig::default());
+        let kind = ChannelKind::new(1);
+        connection.add_channel(kind, bidirectional_settings());
+
+        // build the packet the way flush_channels would have on the sending side
+        connection.channels.get_mut(&kind).unwrap().buffer_send(Bytes::from_static(b"ping")).unwrap();
+        let packets = connection.flush_channels();
+        assert_eq!(packets.len(), 1);
+
+        // a fresh connection plays the role of the receiving peer
+        let mut receiver = Connection::new(addr(), PingConfig::default());
+        receiver.add_channel(kind, bidirectional_settings());
+        receiver.receive_packet(packets[0].clone());
+        let messages = receiver.read_messages();
+        assert_eq!(messages.get(&kind), Some(&vec![Bytes::from_static(b"ping")]));
+    }
+
+    #[test]
+    fn receive_packet_for_an_untracked_channel_is_silently_dropped() {
+        let mut connection = Connection::new(addr(), PingConfig::default());
+        connection.receive_packet(Bytes::from_static(b"\x00\x00garbage"));
+        assert!(connection.read_messages().is_empty());
+    }
+}
@@ -0,0 +1,30 @@
+use std::net::SocketAddr;
+
+/// Events raised by the server for the application to react to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerEvent {
+    Connect(SocketAddr),
+    Disconnect(SocketAddr),
+    /// No packet was received from this client within the configured timeout
+    Timeout(SocketAddr),
+    /// The Noise handshake with this address failed; no session was established and any
+    /// packets it already sent were dropped
+    HandshakeFailed(SocketAddr),
+}
+
+/// Collects events raised during an update so they can be drained by the application once
+/// per frame.
+#[derive(Default)]
+pub(crate) struct EventsBuffer {
+    events: Vec<ServerEvent>,
+}
+
+impl EventsBuffer {
+    pub fn push(&mut self, event: ServerEvent) {
+        self.events.push(event);
+    }
+
+    pub fn drain(&mut self) -> Vec<ServerEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
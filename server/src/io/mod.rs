@@ -0,0 +1,30 @@
+pub(crate) mod noise;
+
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+
+/// A plaintext UDP transport: bytes handed to [`Self::send`] go out on the wire unmodified,
+/// and bytes returned by [`Self::recv`] are exactly what the socket received.
+pub struct Io {
+    socket: UdpSocket,
+}
+
+impl Io {
+    pub fn new(addr: SocketAddr) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self { socket })
+    }
+
+    pub fn send(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        self.socket.send_to(buf, target)
+    }
+
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.socket.recv_from(buf)
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+}
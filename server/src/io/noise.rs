@@ -0,0 +1,389 @@
+use snow::params::NoiseParams;
+use snow::Builder;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+
+const NOISE_PATTERN: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+
+fn noise_params() -> &'static NoiseParams {
+    static PARAMS: OnceLock<NoiseParams> = OnceLock::new();
+    PARAMS.get_or_init(|| NOISE_PATTERN.parse().expect("valid noise pattern string"))
+}
+
+/// Config toggle for the optional Noise-encrypted transport, modeled on the
+/// handshake-then-transport pattern used for peer connections in rust-lightning: a Noise XX
+/// handshake derives per-direction symmetric keys, after which every datagram is encrypted
+/// and authenticated with a per-packet nonce.
+#[derive(Clone, Default)]
+pub struct NoiseConfig {
+    pub enabled: bool,
+    /// This server's static Noise private key
+    pub static_private_key: [u8; 32],
+}
+
+enum PeerState {
+    Handshaking(Box<snow::HandshakeState>),
+    Transport(Box<snow::TransportState>, ReplayWindow),
+}
+
+/// Sliding-window anti-replay check over the nonces of an established transport session, so a
+/// captured ciphertext/nonce pair can't simply be resent and re-authenticated. Tolerates
+/// datagrams arriving out of order within `WINDOW_SIZE` of the highest nonce seen so far, the
+/// same trade-off IPsec/WireGuard-style anti-replay windows make over unreliable transports.
+struct ReplayWindow {
+    /// Highest nonce accepted so far; `None` before the first packet
+    highest: Option<u64>,
+    /// Bit `i` set means `highest - i` has already been accepted
+    bitmap: u64,
+}
+
+const REPLAY_WINDOW_SIZE: u64 = 64;
+
+impl ReplayWindow {
+    fn new() -> Self {
+        Self {
+            highest: None,
+            bitmap: 0,
+        }
+    }
+
+    /// Whether `nonce` is new enough (and not already marked as seen) to be worth decrypting.
+    /// Does not itself mark `nonce` as seen — call [`Self::record`] only once it has also
+    /// passed authentication, so a forged nonce can't be used to desync the window.
+    fn would_accept(&self, nonce: u64) -> bool {
+        match self.highest {
+            None => true,
+            Some(highest) => {
+                if nonce > highest {
+                    true
+                } else {
+                    let diff = highest - nonce;
+                    diff < REPLAY_WINDOW_SIZE && self.bitmap & (1 << diff) == 0
+                }
+            }
+        }
+    }
+
+    /// Mark `nonce` as seen, sliding the window forward if it is the new highest.
+    fn record(&mut self, nonce: u64) {
+        match self.highest {
+            None => {
+                self.highest = Some(nonce);
+                self.bitmap = 1;
+            }
+            Some(highest) if nonce > highest => {
+                let shift = nonce - highest;
+                self.bitmap = if shift >= REPLAY_WINDOW_SIZE {
+                    1
+                } else {
+                    (self.bitmap << shift) | 1
+                };
+                self.highest = Some(nonce);
+            }
+            Some(highest) => {
+                let diff = highest - nonce;
+                self.bitmap |= 1 << diff;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum SessionState {
+    /// No handshake has started for this address yet; the next packet from it should be
+    /// treated as the first Noise handshake message
+    None,
+    Handshaking,
+    Transport,
+}
+
+#[derive(Debug)]
+pub(crate) enum NoiseError {
+    HandshakeFailed,
+    AuthenticationFailed,
+    /// No session (handshaking or established) exists yet for this peer
+    NotReady,
+    /// Noise is enabled but [`NoiseConfig::static_private_key`] was left at its all-zero
+    /// default, which every peer can derive without ever seeing the wire: authenticating
+    /// against it is equivalent to not authenticating at all
+    DegenerateKey,
+}
+
+/// Wraps the plaintext UDP transport with per-peer Noise sessions: a handshake in progress
+/// for an address, or the transport keys to encrypt/decrypt its datagrams once the handshake
+/// has completed.
+pub(crate) struct NoiseTransport {
+    config: NoiseConfig,
+    peers: HashMap<SocketAddr, PeerState>,
+}
+
+impl NoiseTransport {
+    pub fn new(config: NoiseConfig) -> Self {
+        assert!(
+            !config.enabled || config.static_private_key != [0; 32],
+            "NoiseConfig::enabled is true but static_private_key is still the all-zero default; \
+             every peer can derive this key without ever seeing the wire, so it authenticates \
+             nobody. Set a real static_private_key before enabling Noise."
+        );
+        Self {
+            config,
+            peers: HashMap::new(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Handle the first handshake message from a peer we don't have a session for yet,
+    /// returning our reply to send back.
+    pub fn respond_to_handshake(&mut self, addr: SocketAddr, message: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        if self.config.static_private_key == [0; 32] {
+            return Err(NoiseError::DegenerateKey);
+        }
+        let mut handshake = Builder::new(noise_params().clone())
+            .local_private_key(&self.config.static_private_key)
+            .map_err(|_| NoiseError::HandshakeFailed)?
+            .build_responder()
+            .map_err(|_| NoiseError::HandshakeFailed)?;
+
+        let mut discard = [0u8; 1024];
+        handshake
+            .read_message(message, &mut discard)
+            .map_err(|_| NoiseError::HandshakeFailed)?;
+
+        let mut reply = vec![0u8; 1024];
+        let len = handshake
+            .write_message(&[], &mut reply)
+            .map_err(|_| NoiseError::HandshakeFailed)?;
+        reply.truncate(len);
+
+        self.peers.insert(addr, PeerState::Handshaking(Box::new(handshake)));
+        self.promote_if_finished(addr);
+        Ok(reply)
+    }
+
+    /// Feed in the next handshake message for a peer whose handshake is already in progress.
+    pub fn continue_handshake(&mut self, addr: SocketAddr, message: &[u8]) -> Result<(), NoiseError> {
+        let Some(PeerState::Handshaking(handshake)) = self.peers.get_mut(&addr) else {
+            return Err(NoiseError::NotReady);
+        };
+        let mut discard = [0u8; 1024];
+        handshake
+            .read_message(message, &mut discard)
+            .map_err(|_| NoiseError::HandshakeFailed)?;
+        self.promote_if_finished(addr);
+        Ok(())
+    }
+
+    fn promote_if_finished(&mut self, addr: SocketAddr) {
+        let Some(PeerState::Handshaking(handshake)) = self.peers.get(&addr) else {
+            return;
+        };
+        if !handshake.is_handshake_finished() {
+            return;
+        }
+        let Some(PeerState::Handshaking(handshake)) = self.peers.remove(&addr) else {
+            unreachable!("just matched Handshaking above")
+        };
+        if let Ok(transport) = handshake.into_transport_mode() {
+            self.peers.insert(
+                addr,
+                PeerState::Transport(Box::new(transport), ReplayWindow::new()),
+            );
+        }
+    }
+
+    /// Where a peer's Noise session currently stands, so the caller knows whether an incoming
+    /// packet is a handshake message or an encrypted transport packet.
+    pub fn session_state(&self, addr: &SocketAddr) -> SessionState {
+        match self.peers.get(addr) {
+            None => SessionState::None,
+            Some(PeerState::Handshaking(_)) => SessionState::Handshaking,
+            Some(PeerState::Transport(..)) => SessionState::Transport,
+        }
+    }
+
+    /// Encrypt `plaintext` for `addr`, prefixing the ciphertext with the sending nonce so the
+    /// remote can decrypt datagrams that arrive out of order.
+    pub fn encrypt(&mut self, addr: SocketAddr, plaintext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let Some(PeerState::Transport(transport, _)) = self.peers.get_mut(&addr) else {
+            return Err(NoiseError::NotReady);
+        };
+        let nonce = transport.sending_nonce();
+        let mut ciphertext = vec![0u8; plaintext.len() + 16];
+        let len = transport
+            .write_message(plaintext, &mut ciphertext)
+            .map_err(|_| NoiseError::HandshakeFailed)?;
+        ciphertext.truncate(len);
+
+        let mut packet = Vec::with_capacity(8 + ciphertext.len());
+        packet.extend_from_slice(&nonce.to_le_bytes());
+        packet.extend_from_slice(&ciphertext);
+        Ok(packet)
+    }
+
+    /// Decrypt a packet produced by the peer's [`Self::encrypt`]. Returns
+    /// [`NoiseError::AuthenticationFailed`] for a packet that fails authentication, or whose
+    /// nonce falls outside the replay window or repeats one already seen, instead of ever
+    /// handing it to a channel receiver.
+    pub fn decrypt(&mut self, addr: SocketAddr, packet: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let Some(PeerState::Transport(transport, replay)) = self.peers.get_mut(&addr) else {
+            return Err(NoiseError::NotReady);
+        };
+        if packet.len() < 8 {
+            return Err(NoiseError::AuthenticationFailed);
+        }
+        let (nonce_bytes, ciphertext) = packet.split_at(8);
+        let nonce = u64::from_le_bytes(nonce_bytes.try_into().unwrap());
+        if !replay.would_accept(nonce) {
+            return Err(NoiseError::AuthenticationFailed);
+        }
+        transport.set_receiving_nonce(nonce);
+
+        let mut plaintext = vec![0u8; ciphertext.len()];
+        let len = transport
+            .read_message(ciphertext, &mut plaintext)
+            .map_err(|_| NoiseError::AuthenticationFailed)?;
+        plaintext.truncate(len);
+        // only mark the nonce as seen once the packet has actually authenticated, so a forged
+        // nonce on a bogus packet can't be used to desync the window against real traffic
+        replay.record(nonce);
+        Ok(plaintext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr() -> SocketAddr {
+        "127.0.0.1:0".parse().unwrap()
+    }
+
+    fn real_key() -> [u8; 32] {
+        let keypair = Builder::new(noise_params().clone()).generate_keypair().unwrap();
+        keypair.private.try_into().unwrap()
+    }
+
+    #[test]
+    fn new_accepts_a_real_key_when_enabled() {
+        NoiseTransport::new(NoiseConfig {
+            enabled: true,
+            static_private_key: real_key(),
+        });
+    }
+
+    #[test]
+    fn new_accepts_the_default_key_when_disabled() {
+        NoiseTransport::new(NoiseConfig::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "all-zero default")]
+    fn new_panics_on_a_degenerate_key_when_enabled() {
+        NoiseTransport::new(NoiseConfig {
+            enabled: true,
+            static_private_key: [0; 32],
+        });
+    }
+
+    #[test]
+    fn respond_to_handshake_rejects_a_degenerate_key() {
+        // constructed with enabled: false so NoiseTransport::new's own assertion doesn't
+        // already catch it; this is the defense-in-depth check for respond_to_handshake itself
+        let mut transport = NoiseTransport::new(NoiseConfig {
+            enabled: false,
+            static_private_key: [0; 32],
+        });
+        let result = transport.respond_to_handshake(addr(), &[]);
+        assert!(matches!(result, Err(NoiseError::DegenerateKey)));
+    }
+
+    #[test]
+    fn full_handshake_then_encrypt_decrypt_roundtrip() {
+        let server_key = real_key();
+        let mut server = NoiseTransport::new(NoiseConfig {
+            enabled: true,
+            static_private_key: server_key,
+        });
+        let client_key = real_key();
+        let peer = addr();
+
+        let mut client_handshake = Builder::new(noise_params().clone())
+            .local_private_key(&client_key)
+            .unwrap()
+            .build_initiator()
+            .unwrap();
+
+        let mut msg1 = vec![0u8; 1024];
+        let len = client_handshake.write_message(&[], &mut msg1).unwrap();
+        msg1.truncate(len);
+
+        // Noise_XX is a 3-message handshake (-> e, <- e,ee,s,es, -> s,se), so the server isn't
+        // promoted to Transport until it has also processed the client's third message
+        let msg2 = server.respond_to_handshake(peer, &msg1).unwrap();
+        assert_eq!(server.session_state(&peer), SessionState::Handshaking);
+
+        let mut discard = [0u8; 1024];
+        client_handshake.read_message(&msg2, &mut discard).unwrap();
+        let mut msg3 = vec![0u8; 1024];
+        let len = client_handshake.write_message(&[], &mut msg3).unwrap();
+        msg3.truncate(len);
+        assert!(client_handshake.is_handshake_finished());
+        let mut client_transport = client_handshake.into_transport_mode().unwrap();
+
+        server.continue_handshake(peer, &msg3).unwrap();
+        assert_eq!(server.session_state(&peer), SessionState::Transport);
+
+        let nonce = client_transport.sending_nonce();
+        let mut ciphertext = vec![0u8; 64];
+        let len = client_transport.write_message(b"hello server", &mut ciphertext).unwrap();
+        ciphertext.truncate(len);
+        let mut packet = nonce.to_le_bytes().to_vec();
+        packet.extend_from_slice(&ciphertext);
+
+        let plaintext = server.decrypt(peer, &packet).unwrap();
+        assert_eq!(plaintext, b"hello server");
+
+        // a resent copy of the exact same packet must be rejected by the replay window
+        assert!(matches!(
+            server.decrypt(peer, &packet),
+            Err(NoiseError::AuthenticationFailed)
+        ));
+
+        // and the server's own encrypt() path (what heartbeats now go through) produces a
+        // packet long enough to pass the remote's minimum-length check
+        let heartbeat = server.encrypt(peer, &[]).unwrap();
+        assert!(heartbeat.len() >= 8);
+    }
+
+    #[test]
+    fn replay_window_accepts_in_order_and_rejects_repeats() {
+        let mut window = ReplayWindow::new();
+        assert!(window.would_accept(0));
+        window.record(0);
+        assert!(!window.would_accept(0));
+        assert!(window.would_accept(1));
+        window.record(1);
+        assert!(!window.would_accept(0));
+    }
+
+    #[test]
+    fn replay_window_tolerates_reordering_within_the_window() {
+        let mut window = ReplayWindow::new();
+        window.record(10);
+        // nonce 5 arrived late but is still within REPLAY_WINDOW_SIZE of 10
+        assert!(window.would_accept(5));
+        window.record(5);
+        assert!(!window.would_accept(5));
+    }
+
+    #[test]
+    fn replay_window_rejects_nonces_too_far_behind_the_window() {
+        let mut window = ReplayWindow::new();
+        window.record(REPLAY_WINDOW_SIZE * 2);
+        assert!(!window.would_accept(0));
+    }
+}
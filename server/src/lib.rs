@@ -2,6 +2,9 @@
 #![allow(unused)]
 
 pub use config::{NetcodeConfig, ServerConfig};
+pub use events::ServerEvent;
+pub use io::noise::NoiseConfig;
+pub use io::Io;
 pub use ping_manager::PingConfig;
 pub use plugin::{Plugin, PluginConfig};
 pub use server::Server;
@@ -0,0 +1,141 @@
+use lightyear_shared::channel::senders::rtt::RttEstimate;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Smoothing factor for the RTT/jitter EWMA, matching the `ALPHA` used by TCP's RTO estimator.
+const RTT_EWMA_ALPHA: f64 = 0.125;
+
+/// Configures the keepalive/RTT-measurement behavior of a connection's [`PingManager`].
+#[derive(Clone, Debug)]
+pub struct PingConfig {
+    /// Interval for dedicated RTT ping packets. The server loop does not read it and sends no
+    /// ping packets of its own.
+    pub ping_interval: Duration,
+    /// How long a connection can go without sending any packet before an empty heartbeat
+    /// packet is sent, so the remote's idle timer keeps being reset even when no application
+    /// messages are flowing. The heartbeat carries no acks and no RTT sample.
+    pub heartbeat_interval: Duration,
+    /// Number of `heartbeat_interval`s of silence from the remote before the connection is
+    /// considered dead
+    pub timeout: u32,
+}
+
+impl Default for PingConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval: Duration::from_millis(100),
+            heartbeat_interval: Duration::from_secs(1),
+            timeout: 6,
+        }
+    }
+}
+
+/// Tracks round-trip time and liveness for a single connection.
+pub(crate) struct PingManager {
+    config: PingConfig,
+    last_sent: Option<Instant>,
+    last_received: Option<Instant>,
+    rtt: Duration,
+    /// EWMA-smoothed RTT and mean-deviation jitter, updated on every received pong and
+    /// published to `rtt_estimate` for the connection's reliable channels to read.
+    smoothed_rtt: Duration,
+    jitter: Duration,
+    rtt_estimate: RttEstimate,
+    next_ping_id: u16,
+    pending_pings: HashMap<u16, Instant>,
+}
+
+impl PingManager {
+    pub fn new(config: PingConfig) -> Self {
+        Self {
+            config,
+            last_sent: None,
+            last_received: None,
+            rtt: Duration::ZERO,
+            smoothed_rtt: Duration::ZERO,
+            jitter: Duration::ZERO,
+            rtt_estimate: RttEstimate::new(),
+            next_ping_id: 0,
+            pending_pings: HashMap::new(),
+        }
+    }
+
+    pub fn rtt(&self) -> Duration {
+        self.rtt
+    }
+
+    pub fn smoothed_rtt(&self) -> Duration {
+        self.smoothed_rtt
+    }
+
+    pub fn jitter(&self) -> Duration {
+        self.jitter
+    }
+
+    /// A cheaply-cloneable handle to this connection's RTT estimate, to be handed to each of
+    /// its reliable channels via `ChannelContainer::set_rtt_estimate`.
+    pub fn rtt_estimate(&self) -> RttEstimate {
+        self.rtt_estimate.clone()
+    }
+
+    /// Record that a packet (of any kind) was just sent on this connection.
+    pub fn record_send(&mut self, now: Instant) {
+        self.last_sent = Some(now);
+    }
+
+    /// Record that a packet (of any kind) was just received on this connection.
+    pub fn record_receive(&mut self, now: Instant) {
+        self.last_received = Some(now);
+    }
+
+    /// Whether an empty heartbeat packet must be sent right now to keep the connection alive.
+    pub fn needs_heartbeat(&self, now: Instant) -> bool {
+        match self.last_sent {
+            None => true,
+            Some(last_sent) => now.duration_since(last_sent) >= self.config.heartbeat_interval,
+        }
+    }
+
+    /// Whether the remote has been silent long enough that the connection should be
+    /// considered timed out.
+    pub fn is_timed_out(&self, now: Instant) -> bool {
+        match self.last_received {
+            None => false,
+            Some(last_received) => {
+                now.duration_since(last_received)
+                    >= self.config.heartbeat_interval * self.config.timeout
+            }
+        }
+    }
+
+    /// Start tracking a new ping, returning the id to put in the outgoing ping packet.
+    pub fn send_ping(&mut self, now: Instant) -> u16 {
+        let id = self.next_ping_id;
+        self.next_ping_id = self.next_ping_id.wrapping_add(1);
+        self.pending_pings.insert(id, now);
+        id
+    }
+
+    /// Feed in the matching pong for a ping sent with [`Self::send_ping`], updating the RTT
+    /// and the EWMA-smoothed RTT/jitter that reliable channels resize their resend timeout
+    /// from: `smoothed += alpha * (sample - smoothed)`,
+    /// `jitter += alpha * (|sample - smoothed| - jitter)`.
+    pub fn receive_pong(&mut self, id: u16, now: Instant) {
+        let Some(sent_at) = self.pending_pings.remove(&id) else {
+            return;
+        };
+        let sample = now.duration_since(sent_at);
+        self.rtt = sample;
+
+        let sample_secs = sample.as_secs_f64();
+        let smoothed_secs = self.smoothed_rtt.as_secs_f64();
+        let new_smoothed_secs = smoothed_secs + RTT_EWMA_ALPHA * (sample_secs - smoothed_secs);
+        let jitter_secs = self.jitter.as_secs_f64();
+        let new_jitter_secs =
+            jitter_secs + RTT_EWMA_ALPHA * ((sample_secs - new_smoothed_secs).abs() - jitter_secs);
+
+        self.smoothed_rtt = Duration::from_secs_f64(new_smoothed_secs.max(0.0));
+        self.jitter = Duration::from_secs_f64(new_jitter_secs.max(0.0));
+        self.rtt_estimate.set(self.smoothed_rtt, self.jitter);
+    }
+}
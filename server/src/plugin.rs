@@ -0,0 +1,24 @@
+use crate::config::ServerConfig;
+
+/// Configuration needed to set up the server [`Plugin`].
+#[derive(Clone, Default)]
+pub struct PluginConfig {
+    pub server_config: ServerConfig,
+}
+
+impl PluginConfig {
+    pub fn new(server_config: ServerConfig) -> Self {
+        Self { server_config }
+    }
+}
+
+/// Entry point for embedding lightyear's server in an application.
+pub struct Plugin {
+    config: PluginConfig,
+}
+
+impl Plugin {
+    pub fn new(config: PluginConfig) -> Self {
+        Self { config }
+    }
+}
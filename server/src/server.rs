@@ -0,0 +1,232 @@
+use crate::config::ServerConfig;
+use crate::connection::Connection;
+use crate::events::{EventsBuffer, ServerEvent};
+use crate::io::noise::{NoiseTransport, SessionState};
+use crate::io::Io;
+use bytes::Bytes;
+use lightyear_shared::tick::Tick;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+/// A packet with no payload, sent purely to keep a connection alive: it resets the remote's
+/// idle timer. It carries no acks and no ping data.
+const HEARTBEAT_PACKET: &[u8] = &[];
+
+pub struct Server {
+    config: ServerConfig,
+    io: Io,
+    noise: NoiseTransport,
+    connections: HashMap<SocketAddr, Connection>,
+    events: EventsBuffer,
+    tick: Tick,
+}
+
+impl Server {
+    pub fn new(config: ServerConfig, io: Io) -> Self {
+        Self {
+            noise: NoiseTransport::new(config.noise.clone()),
+            config,
+            io,
+            connections: HashMap::new(),
+            events: EventsBuffer::default(),
+            tick: Tick::default(),
+        }
+    }
+
+    /// Run one iteration of the server loop: receive pending packets, advance the tick,
+    /// flush outgoing channel packets, then perform per-connection bookkeeping (heartbeats,
+    /// timeouts).
+    pub fn update(&mut self, now: Instant) {
+        self.receive_packets(now);
+        self.tick = self.tick + 1;
+        self.update_channels(now);
+        self.update_connections(now);
+    }
+
+    /// Advance every connection's channels to the current tick, evict their stale reassembly
+    /// state, and flush whatever packets their senders have ready to go out.
+    fn update_channels(&mut self, now: Instant) {
+        for (addr, connection) in self.connections.iter_mut() {
+            connection.update(now, self.tick);
+            for payload in connection.flush_channels() {
+                if send_packet(&self.io, &mut self.noise, *addr, &payload) {
+                    connection.ping_manager.record_send(now);
+                }
+            }
+        }
+    }
+
+    fn receive_packets(&mut self, now: Instant) {
+        let mut buf = [0u8; 1500];
+        loop {
+            match self.io.recv(&mut buf) {
+                Ok((len, addr)) => {
+                    let payload = if self.noise.enabled() {
+                        match self.receive_noise_packet(addr, &buf[..len]) {
+                            Some(payload) => payload,
+                            None => continue,
+                        }
+                    } else {
+                        buf[..len].to_vec()
+                    };
+                    // the first packet carrying an actual payload (i.e. past the Noise
+                    // handshake, if enabled) is what establishes the connection
+                    self.accept_connection(addr);
+                    if let Some(connection) = self.connections.get_mut(&addr) {
+                        connection.ping_manager.record_receive(now);
+                        if !payload.is_empty() {
+                            // an empty payload is just a heartbeat keeping the connection
+                            // alive, with nothing for the channel layer to decode
+                            connection.receive_packet(Bytes::from(payload));
+                        }
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// Register `addr` as a connected client the first time a packet with an actual payload
+    /// arrives from it, raising a [`ServerEvent::Connect`].
+    fn accept_connection(&mut self, addr: SocketAddr) {
+        if self.connections.contains_key(&addr) {
+            return;
+        }
+        self.connections
+            .insert(addr, Connection::new(addr, self.config.ping.clone()));
+        self.events.push(ServerEvent::Connect(addr));
+    }
+
+    /// Drive `addr`'s Noise session forward with an incoming datagram: continue its handshake,
+    /// or decrypt and authenticate its transport payload. Returns the decrypted application
+    /// payload, or `None` if the packet carried no payload (a handshake message) or failed
+    /// authentication, in which case it never reaches a [`ChannelReceiver`](lightyear_shared::channel::receivers::ChannelReceiver).
+    fn receive_noise_packet(&mut self, addr: SocketAddr, packet: &[u8]) -> Option<Vec<u8>> {
+        match self.noise.session_state(&addr) {
+            SessionState::Transport => self.noise.decrypt(addr, packet).ok(),
+            SessionState::Handshaking => {
+                if self.noise.continue_handshake(addr, packet).is_err() {
+                    self.events.push(ServerEvent::HandshakeFailed(addr));
+                }
+                None
+            }
+            SessionState::None => {
+                match self.noise.respond_to_handshake(addr, packet) {
+                    Ok(reply) => {
+                        let _ = self.io.send(&reply, addr);
+                    }
+                    Err(_) => self.events.push(ServerEvent::HandshakeFailed(addr)),
+                }
+                None
+            }
+        }
+    }
+
+    fn update_connections(&mut self, now: Instant) {
+        let mut timed_out = Vec::new();
+        for (addr, connection) in self.connections.iter_mut() {
+            // hand every channel the ping manager's current RTT estimate; the server loop sends
+            // no pings and calls no receive_pong() itself, so until a sample is recorded
+            // reliable channels resend on their fixed fallback timeout
+            connection.sync_rtt_estimate();
+            if connection.ping_manager.is_timed_out(now) {
+                timed_out.push(*addr);
+                continue;
+            }
+            if connection.ping_manager.needs_heartbeat(now)
+                && send_packet(&self.io, &mut self.noise, *addr, HEARTBEAT_PACKET)
+            {
+                connection.ping_manager.record_send(now);
+            }
+        }
+        for addr in timed_out {
+            self.connections.remove(&addr);
+            self.events.push(ServerEvent::Timeout(addr));
+        }
+    }
+
+    /// Drain the events raised since the last call to this method.
+    pub fn events(&mut self) -> Vec<ServerEvent> {
+        self.events.drain()
+    }
+}
+
+/// Send `payload` to `addr`, encrypting it first when Noise is enabled — mirroring
+/// [`Server::receive_noise_packet`]'s branch on [`NoiseTransport::session_state`] but for the
+/// send direction. A free function (rather than a `&mut self` method) so it can be called from
+/// inside a loop over `self.connections.iter_mut()`, borrowing `io` and `noise` as the disjoint
+/// fields they are instead of requiring a second borrow of all of `self`.
+fn send_packet(io: &Io, noise: &mut NoiseTransport, addr: SocketAddr, payload: &[u8]) -> bool {
+    if noise.enabled() {
+        match noise.encrypt(addr, payload) {
+            Ok(packet) => io.send(&packet, addr).is_ok(),
+            Err(_) => false,
+        }
+    } else {
+        io.send(payload, addr).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::noise::NoiseConfig;
+    use std::net::UdpSocket;
+
+    fn loopback_io() -> Io {
+        Io::new("127.0.0.1:0".parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn receiving_a_packet_from_a_new_address_populates_the_connections_map() {
+        let io = loopback_io();
+        let server_addr = io.local_addr().unwrap();
+        let mut server = Server::new(ServerConfig::default(), io);
+
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client.send_to(b"hello", server_addr).unwrap();
+
+        server.update(Instant::now());
+
+        assert_eq!(server.connections.len(), 1);
+        assert!(matches!(server.events().as_slice(), [ServerEvent::Connect(_)]));
+    }
+
+    #[test]
+    fn send_packet_sends_raw_bytes_when_noise_is_disabled() {
+        let io = loopback_io();
+        let target = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let target_addr = target.local_addr().unwrap();
+        let mut noise = NoiseTransport::new(NoiseConfig::default());
+
+        assert!(send_packet(&io, &mut noise, target_addr, b"heartbeat-ish"));
+
+        let mut buf = [0u8; 32];
+        let (len, _) = target.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"heartbeat-ish");
+    }
+
+    #[test]
+    fn send_packet_refuses_to_fall_back_to_plaintext_when_noise_is_enabled_but_not_yet_established() {
+        let io = loopback_io();
+        let target = UdpSocket::bind("127.0.0.1:0").unwrap();
+        target.set_nonblocking(true).unwrap();
+        let target_addr = target.local_addr().unwrap();
+        let keypair = snow::Builder::new("Noise_XX_25519_ChaChaPoly_BLAKE2s".parse().unwrap())
+            .generate_keypair()
+            .unwrap();
+        let mut noise = NoiseTransport::new(NoiseConfig {
+            enabled: true,
+            static_private_key: keypair.private.try_into().unwrap(),
+        });
+
+        // no handshake has happened yet, so there is no transport session to encrypt under;
+        // this must not silently degrade to sending the payload in the clear
+        assert!(!send_packet(&io, &mut noise, target_addr, b"heartbeat"));
+
+        let mut buf = [0u8; 32];
+        assert!(target.recv_from(&mut buf).is_err());
+    }
+}
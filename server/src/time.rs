@@ -0,0 +1,30 @@
+use std::time::{Duration, Instant};
+
+/// Tracks time for the server loop as a `Duration` elapsed since startup, advanced explicitly
+/// by [`Self::update`] rather than read from `Instant::now()`.
+pub(crate) struct TimeManager {
+    start: Instant,
+    elapsed: Duration,
+}
+
+impl TimeManager {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Advance the clock by `delta`, typically the duration of the last server update.
+    pub fn update(&mut self, delta: Duration) {
+        self.elapsed += delta;
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn now(&self) -> Instant {
+        self.start + self.elapsed
+    }
+}
@@ -1,23 +1,64 @@
 use crate::channel::receivers::ordered_reliable::OrderedReliableReceiver;
 use crate::channel::receivers::sequenced_reliable::SequencedReliableReceiver;
 use crate::channel::receivers::sequenced_unreliable::SequencedUnreliableReceiver;
+use crate::channel::receivers::tick_buffered::TickBufferedReceiver;
 use crate::channel::receivers::unordered_reliable::UnorderedReliableReceiver;
 use crate::channel::receivers::unordered_unreliable::UnorderedUnreliableReceiver;
 use crate::channel::receivers::ChannelReceiver;
 use crate::channel::senders::reliable::ReliableSender;
+use crate::channel::senders::tick_buffered::TickBufferedSender;
 use crate::channel::senders::unreliable::{SequencedUnreliableSender, UnorderedUnreliableSender};
-use crate::channel::senders::ChannelSender;
+use crate::channel::fragment::FragmentInfo;
+use crate::channel::receivers::ChannelReceive;
+use crate::channel::senders::rtt::RttEstimate;
+use crate::channel::senders::{ChannelSend, ChannelSender};
+use crate::tick::Tick;
 use bitcode::{Decode, Encode};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Instant;
 
 /// A Channel is an abstraction for a way to send messages over the network
 /// You can define the direction, ordering, reliability of the channel
+///
+/// On a given peer, only the half of the channel that its [`NetworkRole`] and the channel's
+/// [`ChannelDirection`] actually allow is instantiated: a `ClientToServer` channel on the
+/// server has no `sender`, and a `ServerToClient` channel on the client has no `sender`
+/// either (only a `receiver`).
 pub struct ChannelContainer {
     pub setting: ChannelSettings,
-    pub(crate) receiver: ChannelReceiver,
-    pub(crate) sender: ChannelSender,
+    pub(crate) receiver: Option<ChannelReceiver>,
+    pub(crate) sender: Option<ChannelSender>,
 }
 
+/// Which side of the connection a peer is playing, used to decide which half of a channel
+/// (sender/receiver) is actually usable from that peer given the channel's direction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NetworkRole {
+    Client,
+    Server,
+}
+
+/// Returned when attempting to use a channel in a way its [`ChannelDirection`] forbids for
+/// the local peer, e.g. sending on a `ServerToClient` channel from the client.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ChannelDirectionError {
+    pub direction: ChannelDirection,
+}
+
+impl std::fmt::Display for ChannelDirectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "channel direction {:?} does not allow sending from this peer's role",
+            self.direction
+        )
+    }
+}
+
+impl std::error::Error for ChannelDirectionError {}
+
 pub trait Channel: 'static {
     fn get_builder(settings: ChannelSettings) -> Box<dyn ChannelBuilder>;
 }
@@ -30,34 +71,111 @@ pub trait ChannelBuilder {
 #[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub(crate) struct ChannelHeader {
     pub(crate) kind: ChannelKind,
-    // TODO: add fragmentation data
+    /// Present when this packet only carries one fragment of a larger message; absent for
+    /// messages that fit in a single packet
+    pub(crate) fragment: Option<FragmentInfo>,
+}
+
+/// Serialize `header` length-prefixed ahead of `payload`, so a single packet can carry both a
+/// bitcode-encoded header and a raw application payload after it: `bitcode::decode` expects
+/// the exact encoded length with no trailing bytes, so the two can't just be concatenated
+/// without a length delimiter between them.
+pub(crate) fn encode_packet(header: &ChannelHeader, payload: &[u8]) -> Bytes {
+    let encoded_header = bitcode::encode(header);
+    let mut buf = BytesMut::with_capacity(2 + encoded_header.len() + payload.len());
+    buf.put_u16(encoded_header.len() as u16);
+    buf.extend_from_slice(&encoded_header);
+    buf.extend_from_slice(payload);
+    buf.freeze()
+}
+
+/// Inverse of [`encode_packet`]. Returns `None` for a packet too short to contain its header
+/// length prefix, whose declared header length overruns the packet, or whose header fails to
+/// decode — any of which means the packet is malformed and should be dropped.
+pub(crate) fn decode_packet(mut data: Bytes) -> Option<(ChannelHeader, Bytes)> {
+    if data.remaining() < 2 {
+        return None;
+    }
+    let header_len = data.get_u16() as usize;
+    if data.remaining() < header_len {
+        return None;
+    }
+    let header_bytes = data.split_to(header_len);
+    let header: ChannelHeader = bitcode::decode(&header_bytes).ok()?;
+    Some((header, data))
+}
+
+/// Decode a packet received off the wire and route its payload to the matching channel in
+/// `channels`. Returns whether a matching channel was found for a well-formed packet; a
+/// malformed packet, or one whose [`ChannelKind`] isn't tracked by `channels`, is dropped.
+pub fn receive_packet(channels: &mut HashMap<ChannelKind, ChannelContainer>, data: Bytes) -> bool {
+    let Some((header, payload)) = decode_packet(data) else {
+        return false;
+    };
+    let Some(channel) = channels.get_mut(&header.kind) else {
+        return false;
+    };
+    channel.buffer_recv(header.fragment, payload);
+    true
+}
+
+/// Collect `channel`'s outgoing packets for this tick, each already wrapped in the
+/// [`ChannelHeader`] that identifies it as belonging to `kind` and ready to hand to the
+/// transport.
+pub fn flush_channel(kind: ChannelKind, channel: &mut ChannelContainer) -> Vec<Bytes> {
+    channel
+        .send_packet()
+        .into_iter()
+        .map(|(fragment, payload)| encode_packet(&ChannelHeader { kind, fragment }, &payload))
+        .collect()
 }
 
 impl ChannelContainer {
-    pub fn new(settings: ChannelSettings) -> Self {
-        let receiver: ChannelReceiver;
-        let sender: ChannelSender;
+    /// Build the half(s) of this channel that are actually usable from a peer playing `role`:
+    /// a channel whose [`ChannelDirection`] forbids `role` from sending has no `sender`, and
+    /// likewise no `receiver` if `role` can't receive on it, so neither side allocates state
+    /// it will never use.
+    pub fn new(settings: ChannelSettings, role: NetworkRole) -> Self {
+        if let ChannelMode::TickBuffered(_) = &settings.mode {
+            assert_eq!(
+                settings.direction,
+                ChannelDirection::ClientToServer,
+                "TickBuffered channels only make sense from client to server: the receiver \
+                 releases buffered messages against the server's own tick, which no client has"
+            );
+        }
+
+        let can_send = settings.can_send_to(role);
+        let can_receive = settings.can_receive_from(role);
         let settings_clone = settings.clone();
+
+        let receiver: Option<ChannelReceiver>;
+        let sender: Option<ChannelSender>;
         match settings.mode {
             ChannelMode::UnorderedUnreliable => {
-                receiver = UnorderedUnreliableReceiver::new().into();
-                sender = UnorderedUnreliableSender::new().into();
+                receiver = can_receive.then(|| UnorderedUnreliableReceiver::new().into());
+                sender = can_send.then(|| UnorderedUnreliableSender::new().into());
             }
             ChannelMode::SequencedUnreliable => {
-                receiver = SequencedUnreliableReceiver::new().into();
-                sender = SequencedUnreliableSender::new().into();
+                receiver = can_receive.then(|| SequencedUnreliableReceiver::new().into());
+                sender = can_send.then(|| SequencedUnreliableSender::new().into());
             }
             ChannelMode::UnorderedReliable(reliable_settings) => {
-                receiver = UnorderedReliableReceiver::new().into();
-                sender = ReliableSender::new(reliable_settings).into();
+                receiver = can_receive.then(|| UnorderedReliableReceiver::new().into());
+                sender = can_send.then(|| ReliableSender::new(reliable_settings).into());
             }
             ChannelMode::SequencedReliable(reliable_settings) => {
-                receiver = SequencedReliableReceiver::new().into();
-                sender = ReliableSender::new(reliable_settings).into();
+                receiver = can_receive.then(|| SequencedReliableReceiver::new().into());
+                sender = can_send.then(|| ReliableSender::new(reliable_settings).into());
             }
             ChannelMode::OrderedReliable(reliable_settings) => {
-                receiver = OrderedReliableReceiver::new().into();
-                sender = ReliableSender::new(reliable_settings).into();
+                receiver = can_receive.then(|| OrderedReliableReceiver::new().into());
+                sender = can_send.then(|| ReliableSender::new(reliable_settings).into());
+            }
+            ChannelMode::TickBuffered(tick_buffer_settings) => {
+                receiver =
+                    can_receive.then(|| TickBufferedReceiver::new(tick_buffer_settings.clone()).into());
+                sender = can_send.then(|| TickBufferedSender::new(tick_buffer_settings).into());
             }
         }
         Self {
@@ -66,6 +184,73 @@ impl ChannelContainer {
             sender,
         }
     }
+
+    /// Queue `message` to be sent on this channel, or return an error if this peer's role
+    /// isn't allowed to send on it (see [`ChannelSettings::can_send_to`]).
+    pub fn buffer_send(&mut self, message: Bytes) -> Result<(), ChannelDirectionError> {
+        let Some(sender) = self.sender.as_mut() else {
+            return Err(ChannelDirectionError {
+                direction: self.setting.direction,
+            });
+        };
+        sender.buffer_send(message);
+        Ok(())
+    }
+
+    /// Supply the connection's shared RTT estimate so a reliable channel can size its resend
+    /// timeout from the measured RTT instead of a fixed guess. A no-op for other channel modes
+    /// and for peers that don't hold a sender for this channel.
+    pub fn set_rtt_estimate(&mut self, rtt_estimate: RttEstimate) {
+        if let Some(sender) = self.sender.as_mut() {
+            sender.set_rtt_estimate(rtt_estimate);
+        }
+    }
+
+    /// Route a received packet's payload (and fragmentation data, if any) to the receiver
+    /// half. A no-op if this peer's role doesn't hold a receiver for this channel.
+    pub(crate) fn buffer_recv(&mut self, fragment: Option<FragmentInfo>, data: Bytes) {
+        if let Some(receiver) = self.receiver.as_mut() {
+            receiver.buffer_recv(fragment, data);
+        }
+    }
+
+    /// Drain the messages that are ready to be delivered to the application. Empty if this
+    /// peer's role doesn't hold a receiver for this channel.
+    pub fn read_messages(&mut self) -> Vec<Bytes> {
+        match self.receiver.as_mut() {
+            Some(receiver) => receiver.read_messages(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Collect the packet payloads this channel's sender wants to go out this tick, each
+    /// already paired with the fragmentation data for that payload's header. Empty if this
+    /// peer's role doesn't hold a sender for this channel.
+    pub(crate) fn send_packet(&mut self) -> Vec<(Option<FragmentInfo>, Bytes)> {
+        match self.sender.as_mut() {
+            Some(sender) => sender.send_packet(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Periodic per-tick bookkeeping on the receiver half, e.g. evicting reassembly state for
+    /// messages that will never complete.
+    pub fn update(&mut self, now: Instant) {
+        if let Some(receiver) = self.receiver.as_mut() {
+            receiver.update(now);
+        }
+    }
+
+    /// Advance this channel's notion of the current tick, for the
+    /// [`TickBuffered`](ChannelMode::TickBuffered) mode; a no-op for every other mode.
+    pub fn advance_tick(&mut self, tick: Tick) {
+        if let Some(receiver) = self.receiver.as_mut() {
+            receiver.advance_tick(tick);
+        }
+        if let Some(sender) = self.sender.as_mut() {
+            sender.advance_tick(tick);
+        }
+    }
 }
 
 /// Type of the channel
@@ -113,9 +298,12 @@ pub enum ChannelMode {
     SequencedReliable(ReliableSettings),
     /// Packets will arrive in the correct order at the destination
     OrderedReliable(ReliableSettings),
+    /// Messages are tagged with the client tick they were produced on and only released once
+    /// the server's tick reaches that tick, smoothing out jitter for tick-driven client input
+    TickBuffered(TickBufferSettings),
 }
 
-#[derive(Clone, PartialEq, Default)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
 pub enum ChannelDirection {
     ClientToServer,
     ServerToClient,
@@ -135,4 +323,127 @@ impl ReliableSettings {
             rtt_resend_factor: 1.5,
         }
     }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TickBufferSettings {
+    /// Maximum number of ticks a message can be buffered for (on either side of the current
+    /// tick) before it is dropped instead of delivered
+    pub max_buffer_ticks: u16,
+}
+
+impl ChannelSettings {
+    /// Whether this channel resends unacked messages until they are delivered, i.e. any of
+    /// the `*Reliable` [`ChannelMode`]s.
+    pub fn reliable(&self) -> bool {
+        matches!(
+            self.mode,
+            ChannelMode::UnorderedReliable(_)
+                | ChannelMode::SequencedReliable(_)
+                | ChannelMode::OrderedReliable(_)
+        )
+    }
+
+    /// Whether this channel releases messages against the local tick instead of as soon as
+    /// they arrive, i.e. [`ChannelMode::TickBuffered`].
+    pub fn tick_buffered(&self) -> bool {
+        matches!(self.mode, ChannelMode::TickBuffered(_))
+    }
+
+    /// Whether a peer playing `role` is allowed to send messages on this channel.
+    pub fn can_send_to(&self, role: NetworkRole) -> bool {
+        matches!(
+            (self.direction, role),
+            (ChannelDirection::Bidirectional, _)
+                | (ChannelDirection::ClientToServer, NetworkRole::Client)
+                | (ChannelDirection::ServerToClient, NetworkRole::Server)
+        )
+    }
+
+    /// Whether a peer playing `role` is allowed to receive messages on this channel.
+    pub fn can_receive_from(&self, role: NetworkRole) -> bool {
+        matches!(
+            (self.direction, role),
+            (ChannelDirection::Bidirectional, _)
+                | (ChannelDirection::ClientToServer, NetworkRole::Server)
+                | (ChannelDirection::ServerToClient, NetworkRole::Client)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn unordered_bidirectional_settings() -> ChannelSettings {
+        ChannelSettings {
+            mode: ChannelMode::UnorderedUnreliable,
+            direction: ChannelDirection::Bidirectional,
+        }
+    }
+
+    #[test]
+    fn encode_decode_packet_roundtrips_header_and_payload() {
+        let header = ChannelHeader {
+            kind: ChannelKind::new(7),
+            fragment: None,
+        };
+        let packet = encode_packet(&header, b"hello");
+        let (decoded_header, payload) = decode_packet(packet).expect("well-formed packet decodes");
+        assert_eq!(decoded_header, header);
+        assert_eq!(&payload[..], b"hello");
+    }
+
+    #[test]
+    fn decode_packet_rejects_truncated_input() {
+        assert!(decode_packet(Bytes::from_static(b"\x00")).is_none());
+        // declares a header longer than the remaining bytes actually carry
+        assert!(decode_packet(Bytes::from_static(b"\xff\xffshort")).is_none());
+    }
+
+    #[test]
+    fn receive_packet_routes_payload_to_the_matching_channel() {
+        let kind = ChannelKind::new(1);
+        let mut channels = HashMap::new();
+        channels.insert(
+            kind,
+            ChannelContainer::new(unordered_bidirectional_settings(), NetworkRole::Server),
+        );
+        let packet = encode_packet(
+            &ChannelHeader {
+                kind,
+                fragment: None,
+            },
+            b"payload",
+        );
+        assert!(receive_packet(&mut channels, packet));
+        let messages = channels.get_mut(&kind).unwrap().read_messages();
+        assert_eq!(messages, vec![Bytes::from_static(b"payload")]);
+    }
+
+    #[test]
+    fn receive_packet_drops_a_packet_for_an_unknown_channel() {
+        let mut channels = HashMap::new();
+        let packet = encode_packet(
+            &ChannelHeader {
+                kind: ChannelKind::new(99),
+                fragment: None,
+            },
+            b"payload",
+        );
+        assert!(!receive_packet(&mut channels, packet));
+    }
+
+    #[test]
+    fn flush_channel_wraps_send_packet_output_with_a_header() {
+        let kind = ChannelKind::new(2);
+        let mut channel = ChannelContainer::new(unordered_bidirectional_settings(), NetworkRole::Server);
+        channel.buffer_send(Bytes::from_static(b"outgoing")).unwrap();
+        let packets = flush_channel(kind, &mut channel);
+        assert_eq!(packets.len(), 1);
+        let (header, payload) = decode_packet(packets[0].clone()).unwrap();
+        assert_eq!(header.kind, kind);
+        assert_eq!(&payload[..], b"outgoing");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,112 @@
+use crate::channel::message::MessageId;
+use bitcode::{Decode, Encode};
+use bytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Maximum serialized message size before a payload is split into fragments, chosen to stay
+/// comfortably under a typical UDP MTU once the packet and channel headers are added.
+pub(crate) const FRAGMENT_SIZE: usize = 1150;
+
+/// How long a partial message is kept around waiting for its remaining fragments before it
+/// is evicted, bounding the memory a stream of fragments that never completes can consume.
+pub(crate) const REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Per-fragment metadata carried in the [`ChannelHeader`](crate::channel::ChannelHeader) so
+/// the receiver can put a message's fragments back together in the right order.
+#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) struct FragmentInfo {
+    pub message_id: MessageId,
+    pub fragment_index: u8,
+    pub num_fragments: u8,
+}
+
+/// Split `data` into one or more fragments no larger than [`FRAGMENT_SIZE`], each paired with
+/// the [`FragmentInfo`] that should go in that fragment's `ChannelHeader`. Messages that
+/// already fit in a single fragment are returned unchanged as a single `(info, data)` pair.
+pub(crate) fn fragment_message(message_id: MessageId, data: Bytes) -> Vec<(FragmentInfo, Bytes)> {
+    if data.len() <= FRAGMENT_SIZE {
+        return vec![(
+            FragmentInfo {
+                message_id,
+                fragment_index: 0,
+                num_fragments: 1,
+            },
+            data,
+        )];
+    }
+    let num_fragments = data.len().div_ceil(FRAGMENT_SIZE);
+    assert!(
+        num_fragments <= u8::MAX as usize,
+        "message of {} bytes would need {} fragments, more than the {} we can address",
+        data.len(),
+        num_fragments,
+        u8::MAX
+    );
+    data.chunks(FRAGMENT_SIZE)
+        .enumerate()
+        .map(|(fragment_index, chunk)| {
+            (
+                FragmentInfo {
+                    message_id,
+                    fragment_index: fragment_index as u8,
+                    num_fragments: num_fragments as u8,
+                },
+                Bytes::copy_from_slice(chunk),
+            )
+        })
+        .collect()
+}
+
+struct PartialMessage {
+    num_fragments: u8,
+    fragments: HashMap<u8, Bytes>,
+    last_update: Instant,
+}
+
+/// Reassembles fragmented messages keyed by [`MessageId`]. Incomplete messages that haven't
+/// received a new fragment within [`REASSEMBLY_TIMEOUT`] are dropped by [`Self::evict_stale`]
+/// so fragments that will never complete don't accumulate forever.
+#[derive(Default)]
+pub(crate) struct ReassemblyBuffer {
+    partial: HashMap<MessageId, PartialMessage>,
+}
+
+impl ReassemblyBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed in one received fragment. Returns the fully reassembled message once every
+    /// fragment for its `message_id` has arrived.
+    pub fn receive_fragment(&mut self, info: FragmentInfo, data: Bytes, now: Instant) -> Option<Bytes> {
+        if info.num_fragments == 1 {
+            return Some(data);
+        }
+        let partial = self.partial.entry(info.message_id).or_insert_with(|| PartialMessage {
+            num_fragments: info.num_fragments,
+            fragments: HashMap::new(),
+            last_update: now,
+        });
+        partial.last_update = now;
+        partial.fragments.insert(info.fragment_index, data);
+        if partial.fragments.len() < partial.num_fragments as usize {
+            return None;
+        }
+        let partial = self.partial.remove(&info.message_id)?;
+        let mut buf = BytesMut::new();
+        for fragment_index in 0..partial.num_fragments {
+            buf.extend_from_slice(&partial.fragments[&fragment_index]);
+        }
+        Some(buf.freeze())
+    }
+
+    /// Drop any message whose fragments stopped arriving more than [`REASSEMBLY_TIMEOUT`] ago.
+    /// Unreliable channels call this every tick: a message that can never complete is
+    /// discarded rather than held onto indefinitely.
+    pub fn evict_stale(&mut self, now: Instant) {
+        self.partial
+            .retain(|_, partial| now.duration_since(partial.last_update) < REASSEMBLY_TIMEOUT);
+    }
+}
@@ -0,0 +1,52 @@
+use bitcode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// Identifies a single message within a reliable channel, independent of the packet(s) it
+/// travels in. Used to detect duplicate resends and, once fragmented, to group fragments
+/// back into the original message.
+///
+/// Wraps on overflow so that comparisons stay meaningful across long-running sessions;
+/// use [`MessageId::wrapping_diff`] instead of comparing the inner value directly.
+#[derive(
+    Encode, Decode, Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd,
+)]
+pub(crate) struct MessageId(pub u16);
+
+impl MessageId {
+    /// Signed difference `self - other`, correctly handling wraparound around `u16::MAX`.
+    pub fn wrapping_diff(&self, other: &Self) -> i16 {
+        let a = self.0;
+        let b = other.0;
+        if a == b {
+            0
+        } else if a > b {
+            let diff = a.wrapping_sub(b);
+            if diff > u16::MAX / 2 {
+                -((u16::MAX - diff).wrapping_add(1) as i16)
+            } else {
+                diff as i16
+            }
+        } else {
+            -Self(b).wrapping_diff(self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapping_diff_without_wraparound() {
+        assert_eq!(MessageId(10).wrapping_diff(&MessageId(7)), 3);
+        assert_eq!(MessageId(7).wrapping_diff(&MessageId(10)), -3);
+    }
+
+    #[test]
+    fn wrapping_diff_across_u16_boundary() {
+        let before_wrap = MessageId(u16::MAX);
+        let after_wrap = MessageId(2);
+        assert_eq!(after_wrap.wrapping_diff(&before_wrap), 3);
+        assert_eq!(before_wrap.wrapping_diff(&after_wrap), -3);
+    }
+}
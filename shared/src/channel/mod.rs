@@ -0,0 +1,8 @@
+#[allow(clippy::module_inception)]
+pub mod channel;
+pub mod fragment;
+pub mod message;
+pub mod receivers;
+pub mod senders;
+
+pub use channel::*;
@@ -0,0 +1,122 @@
+pub mod ordered_reliable;
+pub mod sequenced_reliable;
+pub mod sequenced_unreliable;
+pub mod tick_buffered;
+pub mod unordered_reliable;
+pub mod unordered_unreliable;
+
+use crate::channel::fragment::FragmentInfo;
+use crate::channel::receivers::ordered_reliable::OrderedReliableReceiver;
+use crate::channel::receivers::sequenced_reliable::SequencedReliableReceiver;
+use crate::channel::receivers::sequenced_unreliable::SequencedUnreliableReceiver;
+use crate::channel::receivers::tick_buffered::TickBufferedReceiver;
+use crate::channel::receivers::unordered_reliable::UnorderedReliableReceiver;
+use crate::channel::receivers::unordered_unreliable::UnorderedUnreliableReceiver;
+use crate::tick::Tick;
+use bytes::Bytes;
+use std::time::Instant;
+
+/// Common behaviour for the receiving half of a channel: buffer incoming packet payloads
+/// and hand back the messages that are ready to be read by the application.
+pub(crate) trait ChannelReceive {
+    /// Queue the payload of a packet that was just received for this channel, along with the
+    /// fragmentation data from its `ChannelHeader`, if any.
+    fn buffer_recv(&mut self, fragment: Option<FragmentInfo>, data: Bytes);
+
+    /// Return the messages that are ready to be delivered, in delivery order for this mode.
+    fn read_messages(&mut self) -> Vec<Bytes>;
+
+    /// Periodic bookkeeping, e.g. evicting reassembly buffers for messages that will never
+    /// complete. Called once per tick regardless of whether any packet was received.
+    fn update(&mut self, _now: Instant) {}
+}
+
+pub(crate) enum ChannelReceiver {
+    UnorderedUnreliable(UnorderedUnreliableReceiver),
+    SequencedUnreliable(SequencedUnreliableReceiver),
+    UnorderedReliable(UnorderedReliableReceiver),
+    SequencedReliable(SequencedReliableReceiver),
+    OrderedReliable(OrderedReliableReceiver),
+    TickBuffered(TickBufferedReceiver),
+}
+
+impl From<UnorderedUnreliableReceiver> for ChannelReceiver {
+    fn from(receiver: UnorderedUnreliableReceiver) -> Self {
+        Self::UnorderedUnreliable(receiver)
+    }
+}
+
+impl From<SequencedUnreliableReceiver> for ChannelReceiver {
+    fn from(receiver: SequencedUnreliableReceiver) -> Self {
+        Self::SequencedUnreliable(receiver)
+    }
+}
+
+impl From<UnorderedReliableReceiver> for ChannelReceiver {
+    fn from(receiver: UnorderedReliableReceiver) -> Self {
+        Self::UnorderedReliable(receiver)
+    }
+}
+
+impl From<SequencedReliableReceiver> for ChannelReceiver {
+    fn from(receiver: SequencedReliableReceiver) -> Self {
+        Self::SequencedReliable(receiver)
+    }
+}
+
+impl From<OrderedReliableReceiver> for ChannelReceiver {
+    fn from(receiver: OrderedReliableReceiver) -> Self {
+        Self::OrderedReliable(receiver)
+    }
+}
+
+impl From<TickBufferedReceiver> for ChannelReceiver {
+    fn from(receiver: TickBufferedReceiver) -> Self {
+        Self::TickBuffered(receiver)
+    }
+}
+
+impl ChannelReceiver {
+    /// Advance a [`TickBuffered`](ChannelReceiver::TickBuffered) receiver's notion of the
+    /// current tick; a no-op for the other channel modes, which don't release against a tick.
+    pub(crate) fn advance_tick(&mut self, tick: Tick) {
+        if let Self::TickBuffered(receiver) = self {
+            receiver.update_tick(tick);
+        }
+    }
+}
+
+impl ChannelReceive for ChannelReceiver {
+    fn buffer_recv(&mut self, fragment: Option<FragmentInfo>, data: Bytes) {
+        match self {
+            Self::UnorderedUnreliable(receiver) => receiver.buffer_recv(fragment, data),
+            Self::SequencedUnreliable(receiver) => receiver.buffer_recv(fragment, data),
+            Self::UnorderedReliable(receiver) => receiver.buffer_recv(fragment, data),
+            Self::SequencedReliable(receiver) => receiver.buffer_recv(fragment, data),
+            Self::OrderedReliable(receiver) => receiver.buffer_recv(fragment, data),
+            Self::TickBuffered(receiver) => receiver.buffer_recv(fragment, data),
+        }
+    }
+
+    fn read_messages(&mut self) -> Vec<Bytes> {
+        match self {
+            Self::UnorderedUnreliable(receiver) => receiver.read_messages(),
+            Self::SequencedUnreliable(receiver) => receiver.read_messages(),
+            Self::UnorderedReliable(receiver) => receiver.read_messages(),
+            Self::SequencedReliable(receiver) => receiver.read_messages(),
+            Self::OrderedReliable(receiver) => receiver.read_messages(),
+            Self::TickBuffered(receiver) => receiver.read_messages(),
+        }
+    }
+
+    fn update(&mut self, now: Instant) {
+        match self {
+            Self::UnorderedUnreliable(receiver) => receiver.update(now),
+            Self::SequencedUnreliable(receiver) => receiver.update(now),
+            Self::UnorderedReliable(receiver) => receiver.update(now),
+            Self::SequencedReliable(receiver) => receiver.update(now),
+            Self::OrderedReliable(receiver) => receiver.update(now),
+            Self::TickBuffered(receiver) => receiver.update(now),
+        }
+    }
+}
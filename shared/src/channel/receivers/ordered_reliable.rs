@@ -0,0 +1,55 @@
+use crate::channel::fragment::{FragmentInfo, ReassemblyBuffer};
+use crate::channel::message::MessageId;
+use crate::channel::receivers::ChannelReceive;
+use bytes::Bytes;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Instant;
+
+/// Reliable delivery with strict ordering: a fully-reassembled message is held back until
+/// every message with a lower id has already been delivered, so one that reassembles out of
+/// order waits in `waiting_room`.
+#[derive(Default)]
+pub(crate) struct OrderedReliableReceiver {
+    reassembly: ReassemblyBuffer,
+    next_id: MessageId,
+    waiting_room: BTreeMap<MessageId, Bytes>,
+    ready: VecDeque<Bytes>,
+}
+
+impl OrderedReliableReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ChannelReceive for OrderedReliableReceiver {
+    fn buffer_recv(&mut self, fragment: Option<FragmentInfo>, data: Bytes) {
+        let now = Instant::now();
+        let info = fragment.unwrap_or(FragmentInfo {
+            message_id: Default::default(),
+            fragment_index: 0,
+            num_fragments: 1,
+        });
+        let message_id = info.message_id;
+        if message_id.wrapping_diff(&self.next_id) < 0 {
+            // duplicate resend of a fragment belonging to an already-delivered message
+            return;
+        }
+        let Some(message) = self.reassembly.receive_fragment(info, data, now) else {
+            return;
+        };
+        self.waiting_room.insert(message_id, message);
+        while let Some(message) = self.waiting_room.remove(&self.next_id) {
+            self.ready.push_back(message);
+            self.next_id = MessageId(self.next_id.0.wrapping_add(1));
+        }
+    }
+
+    fn read_messages(&mut self) -> Vec<Bytes> {
+        self.ready.drain(..).collect()
+    }
+
+    fn update(&mut self, now: Instant) {
+        self.reassembly.evict_stale(now);
+    }
+}
@@ -0,0 +1,52 @@
+use crate::channel::fragment::{FragmentInfo, ReassemblyBuffer};
+use crate::channel::message::MessageId;
+use crate::channel::receivers::ChannelReceive;
+use bytes::Bytes;
+use std::time::Instant;
+
+/// Reliable delivery, but only the message with the highest id is ever surfaced: an older
+/// message that finishes reassembling after a newer one has already been accepted is
+/// discarded.
+#[derive(Default)]
+pub(crate) struct SequencedReliableReceiver {
+    reassembly: ReassemblyBuffer,
+    most_recent_id: Option<MessageId>,
+    latest: Option<Bytes>,
+}
+
+impl SequencedReliableReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ChannelReceive for SequencedReliableReceiver {
+    fn buffer_recv(&mut self, fragment: Option<FragmentInfo>, data: Bytes) {
+        let now = Instant::now();
+        let info = fragment.unwrap_or(FragmentInfo {
+            message_id: Default::default(),
+            fragment_index: 0,
+            num_fragments: 1,
+        });
+        let message_id = info.message_id;
+        let is_newer = match self.most_recent_id {
+            Some(most_recent) => message_id.wrapping_diff(&most_recent) > 0,
+            None => true,
+        };
+        if !is_newer {
+            return;
+        }
+        if let Some(message) = self.reassembly.receive_fragment(info, data, now) {
+            self.most_recent_id = Some(message_id);
+            self.latest = Some(message);
+        }
+    }
+
+    fn read_messages(&mut self) -> Vec<Bytes> {
+        self.latest.take().into_iter().collect()
+    }
+
+    fn update(&mut self, now: Instant) {
+        self.reassembly.evict_stale(now);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::channel::fragment::{FragmentInfo, ReassemblyBuffer};
+use crate::channel::message::MessageId;
+use crate::channel::receivers::ChannelReceive;
+use bytes::Bytes;
+use std::time::Instant;
+
+/// Only the newest fully-reassembled message is ever kept; an older message that finishes
+/// reassembling after a newer one has already been buffered is discarded. A partial message
+/// whose remaining fragments never arrive is evicted by [`ReassemblyBuffer::evict_stale`].
+#[derive(Default)]
+pub(crate) struct SequencedUnreliableReceiver {
+    reassembly: ReassemblyBuffer,
+    most_recent_id: Option<MessageId>,
+    latest: Option<Bytes>,
+}
+
+impl SequencedUnreliableReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ChannelReceive for SequencedUnreliableReceiver {
+    fn buffer_recv(&mut self, fragment: Option<FragmentInfo>, data: Bytes) {
+        let now = Instant::now();
+        let info = fragment.unwrap_or(FragmentInfo {
+            message_id: Default::default(),
+            fragment_index: 0,
+            num_fragments: 1,
+        });
+        let message_id = info.message_id;
+        let is_newer = match self.most_recent_id {
+            Some(most_recent) => message_id.wrapping_diff(&most_recent) > 0,
+            None => true,
+        };
+        if !is_newer {
+            return;
+        }
+        if let Some(message) = self.reassembly.receive_fragment(info, data, now) {
+            self.most_recent_id = Some(message_id);
+            self.latest = Some(message);
+        }
+    }
+
+    fn read_messages(&mut self) -> Vec<Bytes> {
+        self.latest.take().into_iter().collect()
+    }
+
+    fn update(&mut self, now: Instant) {
+        self.reassembly.evict_stale(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(message_id: u16) -> FragmentInfo {
+        FragmentInfo {
+            message_id: MessageId(message_id),
+            fragment_index: 0,
+            num_fragments: 1,
+        }
+    }
+
+    #[test]
+    fn drops_an_older_message_received_after_a_newer_one() {
+        let mut receiver = SequencedUnreliableReceiver::new();
+        receiver.buffer_recv(Some(info(5)), Bytes::from_static(b"newer"));
+        receiver.buffer_recv(Some(info(3)), Bytes::from_static(b"older"));
+        assert_eq!(receiver.read_messages(), vec![Bytes::from_static(b"newer")]);
+    }
+
+    #[test]
+    fn accepts_a_message_id_that_wrapped_around_u16_max() {
+        let mut receiver = SequencedUnreliableReceiver::new();
+        receiver.buffer_recv(Some(info(u16::MAX)), Bytes::from_static(b"before wrap"));
+        // message id 2 is newer than u16::MAX once it wraps, not older by plain numeric Ord
+        receiver.buffer_recv(Some(info(2)), Bytes::from_static(b"after wrap"));
+        assert_eq!(receiver.read_messages(), vec![Bytes::from_static(b"after wrap")]);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::channel::fragment::FragmentInfo;
+use crate::channel::receivers::ChannelReceive;
+use crate::channel::TickBufferSettings;
+use crate::tick::Tick;
+use bytes::{Buf, Bytes};
+use std::collections::BTreeMap;
+
+/// Receives messages tagged with the client tick they were produced on and only releases
+/// them once the local (server) tick reaches that tick, smoothing out network jitter.
+///
+/// Messages whose tick has already passed by more than [`TickBufferSettings::max_buffer_ticks`]
+/// are dropped instead of being delivered late.
+#[derive(Default)]
+pub(crate) struct TickBufferedReceiver {
+    settings: TickBufferSettings,
+    current_tick: Tick,
+    buffer: BTreeMap<Tick, Bytes>,
+}
+
+impl TickBufferedReceiver {
+    pub fn new(settings: TickBufferSettings) -> Self {
+        Self {
+            settings,
+            current_tick: Tick::default(),
+            buffer: BTreeMap::new(),
+        }
+    }
+
+    /// Advance the receiver's notion of the current (server) tick; called once per tick by
+    /// the connection before messages for that tick are read.
+    pub fn update_tick(&mut self, tick: Tick) {
+        self.current_tick = tick;
+        self.buffer.retain(|message_tick, _| {
+            let age = self.current_tick.wrapping_diff(message_tick);
+            age <= self.settings.max_buffer_ticks as i16
+        });
+    }
+}
+
+impl ChannelReceive for TickBufferedReceiver {
+    fn buffer_recv(&mut self, _fragment: Option<FragmentInfo>, mut data: Bytes) {
+        // client inputs are small enough to never need fragmentation
+        if data.remaining() < 2 {
+            return;
+        }
+        let tick = Tick(data.get_u16_le());
+        let age = self.current_tick.wrapping_diff(&tick);
+        // messages for a tick we have already passed by more than the buffer window are
+        // stale on arrival; a tick tagged further in the future than the buffer window is
+        // also rejected, so a buggy or hostile client can't grow the buffer unboundedly with
+        // bogus far-future ticks
+        if age.unsigned_abs() > self.settings.max_buffer_ticks {
+            return;
+        }
+        self.buffer.insert(tick, data);
+    }
+
+    fn read_messages(&mut self) -> Vec<Bytes> {
+        // `BTreeMap`'s numeric key order breaks down across the u16 wraparound boundary, so
+        // this can't be a `take_while` over `buffer.keys()`: a post-wrap tick sorts below a
+        // still-buffered pre-wrap one despite being later. Use `wrapping_diff` against
+        // `current_tick` instead, which stays correct across the wrap.
+        let ready_ticks: Vec<Tick> = self
+            .buffer
+            .keys()
+            .copied()
+            .filter(|tick| self.current_tick.wrapping_diff(tick) >= 0)
+            .collect();
+        ready_ticks
+            .into_iter()
+            .filter_map(|tick| self.buffer.remove(&tick))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BufMut;
+
+    fn tagged(tick: u16) -> Bytes {
+        let mut buf = bytes::BytesMut::new();
+        buf.put_u16_le(tick);
+        buf.extend_from_slice(b"payload");
+        buf.freeze()
+    }
+
+    fn settings(max_buffer_ticks: u16) -> TickBufferSettings {
+        TickBufferSettings { max_buffer_ticks }
+    }
+
+    #[test]
+    fn drops_a_message_tagged_too_far_in_the_past() {
+        let mut receiver = TickBufferedReceiver::new(settings(3));
+        receiver.update_tick(Tick::new(10));
+        receiver.buffer_recv(None, tagged(0)); // 10 ticks stale, window is 3
+        assert!(receiver.read_messages().is_empty());
+    }
+
+    #[test]
+    fn drops_a_message_tagged_too_far_in_the_future() {
+        let mut receiver = TickBufferedReceiver::new(settings(3));
+        // current tick is 0; a message claiming tick 100 is implausibly far ahead and must
+        // not be allowed to sit in the buffer forever
+        receiver.buffer_recv(None, tagged(100));
+        assert!(receiver.read_messages().is_empty());
+        receiver.update_tick(Tick::new(100));
+        assert!(receiver.read_messages().is_empty());
+    }
+
+    #[test]
+    fn keeps_and_releases_a_message_within_the_buffer_window() {
+        let mut receiver = TickBufferedReceiver::new(settings(3));
+        receiver.buffer_recv(None, tagged(2));
+        assert!(receiver.read_messages().is_empty());
+        receiver.update_tick(Tick::new(2));
+        assert_eq!(receiver.read_messages(), vec![Bytes::from_static(b"payload")]);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::channel::fragment::{FragmentInfo, ReassemblyBuffer};
+use crate::channel::message::MessageId;
+use crate::channel::receivers::ChannelReceive;
+use bytes::Bytes;
+use std::collections::{HashSet, VecDeque};
+use std::time::Instant;
+
+/// Reliable delivery with no ordering guarantee: messages are handed to the application as
+/// soon as all of their fragments have arrived, duplicates (from fragment resends) are
+/// filtered by id.
+#[derive(Default)]
+pub(crate) struct UnorderedReliableReceiver {
+    reassembly: ReassemblyBuffer,
+    seen: HashSet<MessageId>,
+    messages: VecDeque<Bytes>,
+}
+
+impl UnorderedReliableReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ChannelReceive for UnorderedReliableReceiver {
+    fn buffer_recv(&mut self, fragment: Option<FragmentInfo>, data: Bytes) {
+        let now = Instant::now();
+        let info = fragment.unwrap_or(FragmentInfo {
+            message_id: Default::default(),
+            fragment_index: 0,
+            num_fragments: 1,
+        });
+        let message_id = info.message_id;
+        if self.seen.contains(&message_id) {
+            // duplicate resend of a fragment belonging to an already-delivered message
+            return;
+        }
+        if let Some(message) = self.reassembly.receive_fragment(info, data, now) {
+            self.seen.insert(message_id);
+            self.messages.push_back(message);
+        }
+    }
+
+    fn read_messages(&mut self) -> Vec<Bytes> {
+        self.messages.drain(..).collect()
+    }
+
+    fn update(&mut self, now: Instant) {
+        self.reassembly.evict_stale(now);
+    }
+}
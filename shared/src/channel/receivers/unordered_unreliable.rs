@@ -0,0 +1,42 @@
+use crate::channel::fragment::{FragmentInfo, ReassemblyBuffer};
+use crate::channel::receivers::ChannelReceive;
+use bytes::Bytes;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Messages are returned in whatever order they arrived in; nothing is buffered or dropped,
+/// except a partially-fragmented message whose remaining fragments never arrive, which is
+/// evicted by [`ReassemblyBuffer::evict_stale`] instead of being held onto forever.
+#[derive(Default)]
+pub(crate) struct UnorderedUnreliableReceiver {
+    reassembly: ReassemblyBuffer,
+    messages: VecDeque<Bytes>,
+}
+
+impl UnorderedUnreliableReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ChannelReceive for UnorderedUnreliableReceiver {
+    fn buffer_recv(&mut self, fragment: Option<FragmentInfo>, data: Bytes) {
+        let now = Instant::now();
+        let info = fragment.unwrap_or(FragmentInfo {
+            message_id: Default::default(),
+            fragment_index: 0,
+            num_fragments: 1,
+        });
+        if let Some(message) = self.reassembly.receive_fragment(info, data, now) {
+            self.messages.push_back(message);
+        }
+    }
+
+    fn read_messages(&mut self) -> Vec<Bytes> {
+        self.messages.drain(..).collect()
+    }
+
+    fn update(&mut self, now: Instant) {
+        self.reassembly.evict_stale(now);
+    }
+}
@@ -0,0 +1,91 @@
+pub mod reliable;
+pub mod rtt;
+pub mod tick_buffered;
+pub mod unreliable;
+
+use crate::channel::fragment::FragmentInfo;
+use crate::channel::senders::reliable::ReliableSender;
+use crate::channel::senders::rtt::RttEstimate;
+use crate::channel::senders::tick_buffered::TickBufferedSender;
+use crate::channel::senders::unreliable::{SequencedUnreliableSender, UnorderedUnreliableSender};
+use crate::tick::Tick;
+use bytes::Bytes;
+
+/// Common behaviour for the sending half of a channel.
+pub(crate) trait ChannelSend {
+    /// Queue a message from the application to be sent on this channel.
+    fn buffer_send(&mut self, message: Bytes);
+
+    /// Collect the packet payloads that should be sent out this tick, each paired with the
+    /// fragmentation data (if any) that should go in that payload's `ChannelHeader`.
+    fn send_packet(&mut self) -> Vec<(Option<FragmentInfo>, Bytes)>;
+}
+
+pub(crate) enum ChannelSender {
+    UnorderedUnreliable(UnorderedUnreliableSender),
+    SequencedUnreliable(SequencedUnreliableSender),
+    Reliable(ReliableSender),
+    TickBuffered(TickBufferedSender),
+}
+
+impl From<UnorderedUnreliableSender> for ChannelSender {
+    fn from(sender: UnorderedUnreliableSender) -> Self {
+        Self::UnorderedUnreliable(sender)
+    }
+}
+
+impl From<SequencedUnreliableSender> for ChannelSender {
+    fn from(sender: SequencedUnreliableSender) -> Self {
+        Self::SequencedUnreliable(sender)
+    }
+}
+
+impl From<ReliableSender> for ChannelSender {
+    fn from(sender: ReliableSender) -> Self {
+        Self::Reliable(sender)
+    }
+}
+
+impl From<TickBufferedSender> for ChannelSender {
+    fn from(sender: TickBufferedSender) -> Self {
+        Self::TickBuffered(sender)
+    }
+}
+
+impl ChannelSender {
+    /// Supply the connection's shared RTT estimate, if this is a reliable channel; a no-op
+    /// for the other channel modes, which don't resend.
+    pub(crate) fn set_rtt_estimate(&mut self, rtt_estimate: RttEstimate) {
+        if let Self::Reliable(sender) = self {
+            sender.set_rtt_estimate(rtt_estimate);
+        }
+    }
+
+    /// Advance a [`TickBuffered`](ChannelSender::TickBuffered) sender's notion of the current
+    /// tick; a no-op for the other channel modes, which don't tag messages with a tick.
+    pub(crate) fn advance_tick(&mut self, tick: Tick) {
+        if let Self::TickBuffered(sender) = self {
+            sender.update_tick(tick);
+        }
+    }
+}
+
+impl ChannelSend for ChannelSender {
+    fn buffer_send(&mut self, message: Bytes) {
+        match self {
+            Self::UnorderedUnreliable(sender) => sender.buffer_send(message),
+            Self::SequencedUnreliable(sender) => sender.buffer_send(message),
+            Self::Reliable(sender) => sender.buffer_send(message),
+            Self::TickBuffered(sender) => sender.buffer_send(message),
+        }
+    }
+
+    fn send_packet(&mut self) -> Vec<(Option<FragmentInfo>, Bytes)> {
+        match self {
+            Self::UnorderedUnreliable(sender) => sender.send_packet(),
+            Self::SequencedUnreliable(sender) => sender.send_packet(),
+            Self::Reliable(sender) => sender.send_packet(),
+            Self::TickBuffered(sender) => sender.send_packet(),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::channel::fragment::{fragment_message, FragmentInfo};
+use crate::channel::message::MessageId;
+use crate::channel::senders::rtt::RttEstimate;
+use crate::channel::senders::ChannelSend;
+use crate::channel::ReliableSettings;
+use bytes::Bytes;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Resend timeout used until the connection supplies a real RTT estimate, i.e. before the
+/// first ack/pong has come back.
+const FALLBACK_RESEND_TIMEOUT: Duration = Duration::from_millis(300);
+
+struct UnackedFragment {
+    data: Bytes,
+    info: FragmentInfo,
+    last_sent: Option<Instant>,
+}
+
+/// Keeps resending each message until the remote acks it. Used by all three reliable
+/// [`ChannelMode`](crate::channel::ChannelMode)s; ordering/sequencing is applied by the
+/// matching receiver once a message arrives.
+///
+/// Messages larger than the fragment size are split up front; each fragment is tracked and
+/// resent independently, so losing one fragment only costs a resend of that fragment instead
+/// of the whole message.
+pub(crate) struct ReliableSender {
+    settings: ReliableSettings,
+    next_send_id: MessageId,
+    /// Fragments that have been sent at least once and are waiting to be acked, keyed by
+    /// `(message_id, fragment_index)`.
+    unacked: BTreeMap<(MessageId, u8), UnackedFragment>,
+    /// The connection's RTT estimate, shared with its `ping_manager`. `None` until the
+    /// connection layer supplies one via [`Self::set_rtt_estimate`].
+    rtt_estimate: Option<RttEstimate>,
+}
+
+impl ReliableSender {
+    pub fn new(settings: ReliableSettings) -> Self {
+        Self {
+            settings,
+            next_send_id: MessageId::default(),
+            unacked: BTreeMap::new(),
+            rtt_estimate: None,
+        }
+    }
+
+    /// Supply the shared RTT estimate for this connection, so resend timing is computed from
+    /// the measured RTT instead of [`FALLBACK_RESEND_TIMEOUT`].
+    pub fn set_rtt_estimate(&mut self, rtt_estimate: RttEstimate) {
+        self.rtt_estimate = Some(rtt_estimate);
+    }
+
+    /// Mark a single fragment as delivered so it stops being resent. The message is only
+    /// fully acked once every one of its fragments has been acked this way.
+    pub fn receive_ack(&mut self, message_id: MessageId, fragment_index: u8) {
+        self.unacked.remove(&(message_id, fragment_index));
+    }
+
+    fn resend_timeout(&self) -> Duration {
+        self.rtt_estimate
+            .as_ref()
+            .and_then(|rtt| rtt.resend_timeout(self.settings.rtt_resend_factor))
+            .unwrap_or(FALLBACK_RESEND_TIMEOUT)
+    }
+}
+
+fn ready_to_send(now: Instant, resend_timeout: Duration, fragment: &UnackedFragment) -> bool {
+    match fragment.last_sent {
+        None => true,
+        Some(last_sent) => now.duration_since(last_sent) >= resend_timeout,
+    }
+}
+
+impl ChannelSend for ReliableSender {
+    fn buffer_send(&mut self, message: Bytes) {
+        let id = self.next_send_id;
+        self.next_send_id = MessageId(self.next_send_id.0.wrapping_add(1));
+        for (info, data) in fragment_message(id, message) {
+            self.unacked.insert(
+                (info.message_id, info.fragment_index),
+                UnackedFragment {
+                    data,
+                    info,
+                    last_sent: None,
+                },
+            );
+        }
+    }
+
+    fn send_packet(&mut self) -> Vec<(Option<FragmentInfo>, Bytes)> {
+        let now = Instant::now();
+        let resend_timeout = self.resend_timeout();
+        let mut to_send = Vec::new();
+        for fragment in self.unacked.values_mut() {
+            if ready_to_send(now, resend_timeout, fragment) {
+                fragment.last_sent = Some(now);
+                to_send.push((Some(fragment.info), fragment.data.clone()));
+            }
+        }
+        to_send
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A shared, thread-safe snapshot of a connection's RTT estimate: the connection's ping
+/// manager writes the smoothed RTT and jitter to it on every update, and every reliable
+/// channel on that connection reads it to size its resend timeout, without either side
+/// needing to know about the other.
+#[derive(Clone, Default)]
+pub struct RttEstimate {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    has_sample: AtomicBool,
+    smoothed_rtt_micros: AtomicU64,
+    jitter_micros: AtomicU64,
+}
+
+impl RttEstimate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overwrite the current smoothed RTT and jitter, as computed by the connection's ping
+    /// manager's EWMA.
+    pub fn set(&self, smoothed_rtt: Duration, jitter: Duration) {
+        self.inner
+            .smoothed_rtt_micros
+            .store(smoothed_rtt.as_micros() as u64, Ordering::Relaxed);
+        self.inner.jitter_micros.store(jitter.as_micros() as u64, Ordering::Relaxed);
+        self.inner.has_sample.store(true, Ordering::Relaxed);
+    }
+
+    /// The resend timeout a reliable channel should use right now, or `None` if no RTT
+    /// sample has been recorded yet (the caller should fall back to a fixed default).
+    pub fn resend_timeout(&self, rtt_resend_factor: f32) -> Option<Duration> {
+        if !self.inner.has_sample.load(Ordering::Relaxed) {
+            return None;
+        }
+        let smoothed_rtt = Duration::from_micros(self.inner.smoothed_rtt_micros.load(Ordering::Relaxed));
+        let jitter = Duration::from_micros(self.inner.jitter_micros.load(Ordering::Relaxed));
+        // mirrors TCP's RTO = SRTT + 4 * mean-deviation, scaled by the channel's own
+        // rtt_resend_factor on top
+        Some((smoothed_rtt + jitter * 4).mul_f32(rtt_resend_factor))
+    }
+}
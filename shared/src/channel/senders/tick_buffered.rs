@@ -0,0 +1,49 @@
+use crate::channel::fragment::FragmentInfo;
+use crate::channel::senders::ChannelSend;
+use crate::channel::TickBufferSettings;
+use crate::tick::Tick;
+use bytes::{BufMut, Bytes, BytesMut};
+use std::collections::VecDeque;
+
+/// Tags every outgoing message with the client tick it was produced on so the remote's
+/// [`TickBufferedReceiver`](crate::channel::receivers::tick_buffered::TickBufferedReceiver)
+/// can release it once its own clock catches up.
+pub(crate) struct TickBufferedSender {
+    settings: TickBufferSettings,
+    current_tick: Tick,
+    queue: VecDeque<Bytes>,
+}
+
+impl TickBufferedSender {
+    pub fn new(settings: TickBufferSettings) -> Self {
+        Self {
+            settings,
+            current_tick: Tick::default(),
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Set the tick that will be stamped on messages buffered from now on; called once per
+    /// tick by the connection before the application sends its inputs for that tick.
+    pub fn update_tick(&mut self, tick: Tick) {
+        self.current_tick = tick;
+    }
+}
+
+impl ChannelSend for TickBufferedSender {
+    fn buffer_send(&mut self, message: Bytes) {
+        let mut tagged = BytesMut::with_capacity(2 + message.len());
+        tagged.put_u16_le(self.current_tick.0);
+        tagged.extend_from_slice(&message);
+        self.queue.push_back(tagged.freeze());
+    }
+
+    fn send_packet(&mut self) -> Vec<(Option<FragmentInfo>, Bytes)> {
+        // tick-buffered messages are not acked or resent: an unreliable channel dropping the
+        // odd packet is exactly what the buffer window on the receiving end exists to smooth
+        // over, bounded by `settings.max_buffer_ticks`. Client inputs are small enough that
+        // fragmentation is not worth the complexity here.
+        let _ = &self.settings;
+        self.queue.drain(..).map(|data| (None, data)).collect()
+    }
+}
@@ -0,0 +1,67 @@
+use crate::channel::fragment::{fragment_message, FragmentInfo};
+use crate::channel::message::MessageId;
+use crate::channel::senders::ChannelSend;
+use bytes::Bytes;
+use std::collections::VecDeque;
+
+fn fragment_queued_messages(
+    queue: &mut VecDeque<Bytes>,
+    next_message_id: &mut MessageId,
+) -> Vec<(Option<FragmentInfo>, Bytes)> {
+    queue
+        .drain(..)
+        .flat_map(|message| {
+            let id = *next_message_id;
+            *next_message_id = MessageId(next_message_id.0.wrapping_add(1));
+            fragment_message(id, message)
+        })
+        .map(|(info, data)| (Some(info), data))
+        .collect()
+}
+
+/// Sends every message exactly once and never resends; packets may be lost in transit.
+#[derive(Default)]
+pub(crate) struct UnorderedUnreliableSender {
+    next_message_id: MessageId,
+    queue: VecDeque<Bytes>,
+}
+
+impl UnorderedUnreliableSender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ChannelSend for UnorderedUnreliableSender {
+    fn buffer_send(&mut self, message: Bytes) {
+        self.queue.push_back(message);
+    }
+
+    fn send_packet(&mut self) -> Vec<(Option<FragmentInfo>, Bytes)> {
+        fragment_queued_messages(&mut self.queue, &mut self.next_message_id)
+    }
+}
+
+/// Same as [`UnorderedUnreliableSender`]; sequencing is enforced on the receiving end by
+/// discarding anything older than the newest message seen.
+#[derive(Default)]
+pub(crate) struct SequencedUnreliableSender {
+    next_message_id: MessageId,
+    queue: VecDeque<Bytes>,
+}
+
+impl SequencedUnreliableSender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ChannelSend for SequencedUnreliableSender {
+    fn buffer_send(&mut self, message: Bytes) {
+        self.queue.push_back(message);
+    }
+
+    fn send_packet(&mut self) -> Vec<(Option<FragmentInfo>, Bytes)> {
+        fragment_queued_messages(&mut self.queue, &mut self.next_message_id)
+    }
+}
@@ -0,0 +1,5 @@
+#![allow(dead_code)]
+#![allow(unused)]
+
+pub mod channel;
+pub mod tick;
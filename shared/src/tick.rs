@@ -0,0 +1,80 @@
+use bitcode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// A monotonically increasing simulation step, shared by the client and server clocks.
+///
+/// Wraps on overflow so that comparisons stay meaningful across long-running sessions;
+/// use [`Tick::wrapping_diff`] instead of subtracting the inner value directly.
+#[derive(
+    Encode, Decode, Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd,
+)]
+pub struct Tick(pub u16);
+
+impl Tick {
+    pub const fn new(tick: u16) -> Self {
+        Self(tick)
+    }
+
+    /// Signed difference `self - other`, correctly handling wraparound around `u16::MAX`.
+    pub fn wrapping_diff(&self, other: &Self) -> i16 {
+        let a = self.0;
+        let b = other.0;
+        if a == b {
+            0
+        } else if a > b {
+            let diff = a.wrapping_sub(b);
+            if diff > u16::MAX / 2 {
+                -((u16::MAX - diff).wrapping_add(1) as i16)
+            } else {
+                diff as i16
+            }
+        } else {
+            -Self(b).wrapping_diff(self)
+        }
+    }
+}
+
+impl std::ops::Add<u16> for Tick {
+    type Output = Tick;
+    fn add(self, rhs: u16) -> Self::Output {
+        Tick(self.0.wrapping_add(rhs))
+    }
+}
+
+impl std::ops::Sub<u16> for Tick {
+    type Output = Tick;
+    fn sub(self, rhs: u16) -> Self::Output {
+        Tick(self.0.wrapping_sub(rhs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapping_diff_is_zero_for_equal_ticks() {
+        assert_eq!(Tick::new(5).wrapping_diff(&Tick::new(5)), 0);
+    }
+
+    #[test]
+    fn wrapping_diff_without_wraparound() {
+        assert_eq!(Tick::new(10).wrapping_diff(&Tick::new(7)), 3);
+        assert_eq!(Tick::new(7).wrapping_diff(&Tick::new(10)), -3);
+    }
+
+    #[test]
+    fn wrapping_diff_across_u16_boundary() {
+        // tick 2 is 3 ticks after tick 65535 (wrapped: 65535 -> 0 -> 1 -> 2)
+        let before_wrap = Tick::new(u16::MAX);
+        let after_wrap = Tick::new(2);
+        assert_eq!(after_wrap.wrapping_diff(&before_wrap), 3);
+        assert_eq!(before_wrap.wrapping_diff(&after_wrap), -3);
+    }
+
+    #[test]
+    fn add_and_sub_wrap_around_u16_max() {
+        assert_eq!(Tick::new(u16::MAX) + 1, Tick::new(0));
+        assert_eq!(Tick::new(0) - 1, Tick::new(u16::MAX));
+    }
+}